@@ -12,6 +12,7 @@
 //! use lpc82x_hal::Peripherals;
 //! use lpc82x_hal::usart::{
 //!     BaudRate,
+//!     Config,
 //!     USART,
 //! };
 //!
@@ -45,6 +46,7 @@
 //!     &mut syscon.handle,
 //!     u0_rxd,
 //!     u0_txd,
+//!     Config::default(),
 //! );
 //!
 //! // Use a blocking method to write a string
@@ -56,16 +58,19 @@
 //! [examples in the repository]: https://github.com/lpc-rs/lpc8xx-hal/tree/master/lpc82x-hal/examples
 
 use core::fmt;
+use core::marker::PhantomData;
 use core::ops::Deref;
+use core::sync::atomic::{compiler_fence, Ordering};
 
 use embedded_hal::blocking::serial::write::Default as BlockingWriteDefault;
+use embedded_hal::digital::v2::OutputPin;
 use embedded_hal::serial::{Read, Write};
 use nb::{self, block};
 use void::Void;
 
 use crate::{
     dma, init_state,
-    pac::{self, usart0::TXDAT, Interrupt, NVIC},
+    pac::{self, usart0::{RXDAT, TXDAT}, Interrupt, NVIC},
     swm::{self, FunctionTrait, PinTrait},
     syscon::{self, PeripheralClock},
 };
@@ -81,6 +86,7 @@ use crate::{
 /// [module documentation]: index.html
 pub struct USART<UsartX, State = init_state::Enabled> {
     usart: UsartX,
+    config: Config,
     _state: State,
 }
 
@@ -88,6 +94,7 @@ impl<UsartX> USART<UsartX, init_state::Disabled> {
     pub(crate) fn new(usart: UsartX) -> Self {
         USART {
             usart: usart,
+            config: Config::default(),
             _state: init_state::Disabled,
         }
     }
@@ -121,11 +128,73 @@ where
     /// [`BaudRate`]: struct.BaudRate.html
     /// [module documentation]: index.html
     pub fn enable<'a, Rx, Tx, CLOCK>(
+        self,
+        clock: &CLOCK,
+        syscon: &mut syscon::Handle,
+        rx: swm::Function<UsartX::Rx, swm::state::Assigned<Rx>>,
+        tx: swm::Function<UsartX::Tx, swm::state::Assigned<Tx>>,
+        config: Config,
+    ) -> USART<UsartX, init_state::Enabled>
+    where
+        Rx: PinTrait,
+        Tx: PinTrait,
+        UsartX::Rx: FunctionTrait<Rx>,
+        UsartX::Tx: FunctionTrait<Tx>,
+        CLOCK: PeripheralClock<UsartX>,
+    {
+        self.enable_internal(clock, syscon, rx, tx, config, false)
+    }
+
+    /// Enable the USART, with hardware RTS/CTS flow control
+    ///
+    /// Like [`enable`], but additionally takes the SWM movable functions for
+    /// this USART's CTS and RTS pins, and sets up the peripheral to use them
+    /// for hardware flow control: outgoing data is only transmitted while the
+    /// remote end asserts CTS, and RTS is driven by the hardware to tell the
+    /// remote end when this USART's receive buffer is full.
+    ///
+    /// This method is only available, if `USART` is in the [`Disabled`]
+    /// state. Code that attempts to call this method when the peripheral is
+    /// already enabled will not compile.
+    ///
+    /// Consumes this instance of `USART` and returns another instance that
+    /// has its `State` type parameter set to [`Enabled`].
+    ///
+    /// [`enable`]: #method.enable
+    /// [`Disabled`]: ../init_state/struct.Disabled.html
+    /// [`Enabled`]: ../init_state/struct.Enabled.html
+    pub fn enable_with_flow_control<Rx, Tx, Cts, Rts, CLOCK>(
+        self,
+        clock: &CLOCK,
+        syscon: &mut syscon::Handle,
+        rx: swm::Function<UsartX::Rx, swm::state::Assigned<Rx>>,
+        tx: swm::Function<UsartX::Tx, swm::state::Assigned<Tx>>,
+        _cts: swm::Function<UsartX::Cts, swm::state::Assigned<Cts>>,
+        _rts: swm::Function<UsartX::Rts, swm::state::Assigned<Rts>>,
+        config: Config,
+    ) -> USART<UsartX, init_state::Enabled>
+    where
+        Rx: PinTrait,
+        Tx: PinTrait,
+        Cts: PinTrait,
+        Rts: PinTrait,
+        UsartX::Rx: FunctionTrait<Rx>,
+        UsartX::Tx: FunctionTrait<Tx>,
+        UsartX::Cts: FunctionTrait<Cts>,
+        UsartX::Rts: FunctionTrait<Rts>,
+        CLOCK: PeripheralClock<UsartX>,
+    {
+        self.enable_internal(clock, syscon, rx, tx, config, true)
+    }
+
+    fn enable_internal<Rx, Tx, CLOCK>(
         mut self,
         clock: &CLOCK,
         syscon: &mut syscon::Handle,
-        _: swm::Function<UsartX::Rx, swm::state::Assigned<Rx>>,
-        _: swm::Function<UsartX::Tx, swm::state::Assigned<Tx>>,
+        _rx: swm::Function<UsartX::Rx, swm::state::Assigned<Rx>>,
+        _tx: swm::Function<UsartX::Tx, swm::state::Assigned<Tx>>,
+        config: Config,
+        flow_control: bool,
     ) -> USART<UsartX, init_state::Enabled>
     where
         Rx: PinTrait,
@@ -148,26 +217,67 @@ where
 
         self.usart.cfg.modify(|_, w| {
             w.enable().enabled();
-            w.datalen().bit_8();
-            w.paritysel().no_parity();
-            w.stoplen().bit_1();
-            w.ctsen().disabled();
+            match effective_data_len(&config) {
+                DataLength::Bits7 => w.datalen().bit_7(),
+                DataLength::Bits8 => w.datalen().bit_8(),
+                DataLength::Bits9 => w.datalen().bit_9(),
+            };
+            match config.parity {
+                Parity::None => w.paritysel().no_parity(),
+                Parity::Even => w.paritysel().even_parity(),
+                Parity::Odd => w.paritysel().odd_parity(),
+            };
+            match config.stop_bits {
+                StopBits::Bits1 => w.stoplen().bit_1(),
+                StopBits::Bits2 => w.stoplen().bit_2(),
+            };
+            if flow_control {
+                w.ctsen().enabled();
+            } else {
+                w.ctsen().disabled();
+            }
             w.syncen().asynchronous_mode();
-            w.loop_().normal();
-            w.autoaddr().disabled();
+            if config.loopback {
+                w.loop_().loopback();
+            } else {
+                w.loop_().normal();
+            }
+            match config.mode {
+                Mode::Standard => w.autoaddr().disabled(),
+                Mode::MultiDrop { auto_address, .. } if auto_address => w.autoaddr().enabled(),
+                Mode::MultiDrop { .. } => w.autoaddr().disabled(),
+            };
             w.rxpol().standard();
             w.txpol().standard()
         });
 
         self.usart.ctl.modify(|_, w| {
             w.txbrken().normal();
-            w.addrdet().disabled();
+            match config.mode {
+                Mode::Standard => w.addrdet().disabled(),
+                Mode::MultiDrop { .. } => w.addrdet().enabled(),
+            };
             w.txdis().enabled();
-            w.autobaud().disabled()
+            if config.autobaud {
+                w.autobaud().enabled();
+            } else {
+                w.autobaud().disabled();
+            }
+            w
         });
 
+        if let Mode::MultiDrop { address, .. } = config.mode {
+            // See user manual, section 13.6.13: this is the address that
+            // hardware address detection/matching compares incoming address
+            // bytes against.
+            self.usart
+                .addr
+                .write(|w| unsafe { w.address().bits(address) });
+        }
+
         USART {
             usart: self.usart,
+            config,
             _state: init_state::Enabled(()),
         }
     }
@@ -193,6 +303,7 @@ where
 
         USART {
             usart: self.usart,
+            config: self.config,
             _state: init_state::Disabled,
         }
     }
@@ -207,6 +318,72 @@ where
         unsafe { NVIC::unmask(UsartX::INTERRUPT) };
     }
 
+    /// Enable an interrupt event
+    ///
+    /// Enables the given [`Event`] to trigger the USART interrupt. Note that
+    /// the interrupt also needs to be unmasked in the NVIC, or it will never
+    /// fire; see [`enable_interrupts`].
+    ///
+    /// [`Event`]: enum.Event.html
+    /// [`enable_interrupts`]: #method.enable_interrupts
+    pub fn listen(&mut self, event: Event) {
+        self.usart.intenset.write(|w| match event {
+            Event::RxReady => w.rxrdyen().set_bit(),
+            Event::TxReady => w.txrdyen().set_bit(),
+            Event::TxIdle => w.txidleen().set_bit(),
+            Event::Overrun => w.overrunen().set_bit(),
+            Event::FramingError => w.framerren().set_bit(),
+            Event::ParityError => w.parityerren().set_bit(),
+            Event::RxNoise => w.rxnoiseen().set_bit(),
+            Event::RxBreak => w.deltarxbrken().set_bit(),
+            Event::DeltaCts => w.deltactsen().set_bit(),
+            Event::AutoBaudError => w.abererren().set_bit(),
+        });
+    }
+
+    /// Disable an interrupt event
+    ///
+    /// Disables the given [`Event`] from triggering the USART interrupt.
+    ///
+    /// [`Event`]: enum.Event.html
+    pub fn unlisten(&mut self, event: Event) {
+        self.usart.intenclr.write(|w| match event {
+            Event::RxReady => w.rxrdyclr().set_bit(),
+            Event::TxReady => w.txrdyclr().set_bit(),
+            Event::TxIdle => w.txidleclr().set_bit(),
+            Event::Overrun => w.overrunclr().set_bit(),
+            Event::FramingError => w.framerrclr().set_bit(),
+            Event::ParityError => w.parityerrclr().set_bit(),
+            Event::RxNoise => w.rxnoiseclr().set_bit(),
+            Event::RxBreak => w.deltarxbrkclr().set_bit(),
+            Event::DeltaCts => w.deltactsclr().set_bit(),
+            Event::AutoBaudError => w.abererrclr().set_bit(),
+        });
+    }
+
+    /// Query whether the given event is currently triggered
+    ///
+    /// Reads the current status of the given [`Event`] from the `STAT`
+    /// register, regardless of whether the event is currently listened to.
+    ///
+    /// [`Event`]: enum.Event.html
+    pub fn is_event_triggered(&self, event: Event) -> bool {
+        let stat = self.usart.stat.read();
+
+        match event {
+            Event::RxReady => stat.rxrdy().bit_is_set(),
+            Event::TxReady => stat.txrdy().bit_is_set(),
+            Event::TxIdle => stat.txidle().bit_is_set(),
+            Event::Overrun => stat.overrunint().bit_is_set(),
+            Event::FramingError => stat.framerrint().bit_is_set(),
+            Event::ParityError => stat.parityerrint().bit_is_set(),
+            Event::RxNoise => stat.rxnoiseint().bit_is_set(),
+            Event::RxBreak => stat.deltarxbrk().bit_is_set(),
+            Event::DeltaCts => stat.deltacts().bit_is_set(),
+            Event::AutoBaudError => stat.abererr().bit_is_set(),
+        }
+    }
+
     /// Return USART receiver
     pub fn rx(&self) -> Receiver<UsartX> {
         Receiver(self)
@@ -216,6 +393,147 @@ where
     pub fn tx(&self) -> Transmitter<UsartX> {
         Transmitter(self)
     }
+
+    /// Split the USART into independent transmitter and receiver halves
+    ///
+    /// Unlike [`tx`]/[`rx`], which borrow from this `USART`, the returned
+    /// [`Tx`]/[`Rx`] are owned and `Send`, so they can be moved into
+    /// different tasks or interrupt handlers (for example, one driven by the
+    /// RXRDY interrupt, the other by a DMA completion handler) without either
+    /// one aliasing the other.
+    ///
+    /// The frame format configured via [`enable`] carries over to the
+    /// returned [`Rx`].
+    ///
+    /// [`tx`]: #method.tx
+    /// [`rx`]: #method.rx
+    /// [`enable`]: struct.USART.html#method.enable
+    /// [`Tx`]: struct.Tx.html
+    /// [`Rx`]: struct.Rx.html
+    pub fn split(self) -> (Tx<UsartX>, Rx<UsartX>) {
+        (
+            Tx {
+                _usart: PhantomData,
+            },
+            Rx {
+                config: self.config,
+                _usart: PhantomData,
+            },
+        )
+    }
+
+    /// Receive into `buffer` via DMA, until the line goes idle
+    ///
+    /// Programs `channel` to transfer up to `buffer.len()` bytes from the
+    /// receiver into `buffer`, and polls `STAT.rxidle`, which is set once the
+    /// line has stayed high for one character time after the last byte was
+    /// received. Returns as soon as either the DMA transfer completes or the
+    /// line goes idle, whichever happens first, with the number of bytes
+    /// actually received.
+    ///
+    /// This is useful for receiving frames of unknown length (for example,
+    /// one AT command response at a time) without paying for a per-byte
+    /// interrupt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is longer than the range supported by the DMA
+    /// channel's transfer count.
+    pub fn read_until_idle<C>(
+        &mut self,
+        buffer: &mut [u8],
+        channel: &mut dma::Channel<C, init_state::Enabled>,
+    ) -> usize
+    where
+        C: dma::Trigger<UsartX>,
+    {
+        // Clear any stale idle flag left over from a previous reception, so
+        // we don't mistake it for this one.
+        self.usart.stat.write(|w| w.rxidle().set_bit());
+
+        channel.start_transfer(self.rx(), buffer);
+
+        // Polled directly, like every other blocking wait in this file.
+        // `RXIDLE` is never unmasked via `intenset`: this USART's NVIC
+        // interrupt may already be enabled for unrelated events (see
+        // `listen`), and there is no `Event::RxIdle` for an ISR to
+        // acknowledge it through, so unmasking it here would risk an
+        // interrupt that never gets cleared until this loop gets around to
+        // it.
+        loop {
+            if self.usart.stat.read().rxidle().bit_is_set() {
+                channel.abort();
+                break;
+            }
+            if channel.is_complete() {
+                break;
+            }
+        }
+
+        // The DMA controller writes `buffer` behind the CPU's back; make sure
+        // the compiler doesn't reorder the reads the caller is about to do on
+        // `buffer` across this point.
+        compiler_fence(Ordering::SeqCst);
+
+        let remaining = channel.remaining_transfer_count();
+        buffer.len().saturating_sub(remaining)
+    }
+
+    /// Arm the hardware baud-rate auto-detector
+    ///
+    /// Once armed, the USART measures the next incoming 0x55 sync character
+    /// and uses it to populate `BRG` automatically, instead of relying on a
+    /// baud rate configured at compile time.
+    ///
+    /// [`Event::AutoBaudError`] only fires if detection *fails* (timeout or a
+    /// framing error on the sync character); a successful detection clears
+    /// `CTL.autobaud` without raising any STAT event, so that event cannot be
+    /// used to learn when detection has finished either way. To notice
+    /// completion, poll [`is_autobaud_active`] (for example from another
+    /// event's interrupt handler, or off a timer), or listen for
+    /// [`Event::AutoBaudError`] if you only care about the failure case.
+    ///
+    /// [`Event::AutoBaudError`]: enum.Event.html#variant.AutoBaudError
+    /// [`is_autobaud_active`]: #method.is_autobaud_active
+    pub fn start_autobaud(&mut self) {
+        self.usart.ctl.modify(|_, w| w.autobaud().enabled());
+    }
+
+    /// Whether the baud-rate auto-detector armed by [`start_autobaud`] is
+    /// still measuring
+    ///
+    /// The hardware clears `CTL.autobaud` by itself once detection completes
+    /// or fails, so this returns `false` once that has happened.
+    ///
+    /// [`start_autobaud`]: #method.start_autobaud
+    pub fn is_autobaud_active(&self) -> bool {
+        self.usart.ctl.read().autobaud().is_enabled()
+    }
+
+    /// Stop matching address bytes, to let the following data bytes through
+    ///
+    /// Only meaningful in [`Mode::MultiDrop`]. Per the user manual, section
+    /// 13.6.13, address detection needs to be turned off after a matching
+    /// address byte has been seen, or the data bytes that follow it (which
+    /// have their 9th bit clear) never reach [`Receiver::read`]/[`Rx::read`]
+    /// at all; see [`Mode::MultiDrop`] for the full receive sequence. Call
+    /// [`enable_address_detection`] again once the message is done, so the
+    /// next address byte on the bus is recognized as one.
+    ///
+    /// [`Mode::MultiDrop`]: enum.Mode.html#variant.MultiDrop
+    /// [`Receiver::read`]: struct.Receiver.html
+    /// [`Rx::read`]: struct.Rx.html
+    /// [`enable_address_detection`]: #method.enable_address_detection
+    pub fn disable_address_detection(&mut self) {
+        self.usart.ctl.modify(|_, w| w.addrdet().disabled());
+    }
+
+    /// Resume matching address bytes, after [`disable_address_detection`]
+    ///
+    /// [`disable_address_detection`]: #method.disable_address_detection
+    pub fn enable_address_detection(&mut self) {
+        self.usart.ctl.modify(|_, w| w.addrdet().enabled());
+    }
 }
 
 impl<UsartX, State> USART<UsartX, State> {
@@ -237,131 +555,291 @@ impl<UsartX, State> USART<UsartX, State> {
 }
 
 /// USART receiver
+///
+/// Interrupts for the receiver are managed through [`USART::listen`] and
+/// [`USART::unlisten`] on the peripheral itself, rather than on this
+/// transient borrow.
+///
+/// [`USART::listen`]: struct.USART.html#method.listen
+/// [`USART::unlisten`]: struct.USART.html#method.unlisten
 pub struct Receiver<'usart, UsartX: 'usart>(&'usart USART<UsartX>);
 
-impl<'usart, UsartX> Receiver<'usart, UsartX>
+impl<'usart, UsartX> Read<u8> for Receiver<'usart, UsartX>
 where
     UsartX: Peripheral,
 {
-    /// Enable the RXRDY interrupt
-    ///
-    /// The interrupt will not actually work unless the interrupts for this
-    /// peripheral have also been enabled via the NVIC. See
-    /// [`enable_interrupts`].
+    type Error = Error;
+
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        read_usart(&self.0.usart, self.0.config)
+    }
+}
+
+impl<'usart, UsartX> dma::Source for Receiver<'usart, UsartX>
+where
+    UsartX: Peripheral,
+{
+    type Error = Void;
+
+    fn wait(&mut self) -> nb::Result<(), Self::Error> {
+        if self.0.usart.stat.read().rxrdy().bit_is_clear() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        Ok(())
+    }
+
+    fn start_addr(&mut self) -> *const u8 {
+        &self.0.usart.rxdat as *const _ as *const RXDAT as *const u8
+    }
+}
+
+/// USART transmitter
+///
+/// Interrupts for the transmitter are managed through [`USART::listen`] and
+/// [`USART::unlisten`] on the peripheral itself, rather than on this
+/// transient borrow.
+///
+/// [`USART::listen`]: struct.USART.html#method.listen
+/// [`USART::unlisten`]: struct.USART.html#method.unlisten
+pub struct Transmitter<'usart, UsartX: 'usart>(&'usart USART<UsartX>);
+
+impl<'usart, UsartX> Transmitter<'usart, UsartX>
+where
+    UsartX: Peripheral,
+{
+    /// Drive `pin` high for the duration of `f`, then wait for the line to
+    /// go idle before releasing it again
     ///
-    /// [`enable_interrupts`]: #method.enable_interrupts
-    pub fn enable_rxrdy_interrupt(&mut self) {
-        self.0.usart.intenset.write(|w| w.rxrdyen().set_bit());
+    /// Intended for half-duplex RS-485/multidrop transceivers, which need a
+    /// driver-enable pin (often the same pin used as the hardware RTS output,
+    /// reassigned to plain GPIO) driven high while transmitting and low
+    /// otherwise.
+    pub fn write_with_driver_enable<P>(&mut self, pin: &mut P, f: impl FnOnce(&mut Self))
+    where
+        P: OutputPin,
+    {
+        write_with_driver_enable(self, pin, f)
     }
+}
 
-    /// Disable the RXRDY interrupt
-    pub fn disable_rxrdy_interrupt(&mut self) {
-        self.0.usart.intenclr.write(|w| w.rxrdyclr().set_bit());
+impl<'usart, UsartX> Write<u8> for Transmitter<'usart, UsartX>
+where
+    UsartX: Peripheral,
+{
+    type Error = Void;
+
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        write_usart(&self.0.usart, word)
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        flush_usart(&self.0.usart)
     }
 }
 
-impl<'usart, UsartX> Read<u8> for Receiver<'usart, UsartX>
+impl<'usart, UsartX> BlockingWriteDefault<u8> for Transmitter<'usart, UsartX> where
+    UsartX: Peripheral
+{
+}
+
+impl<'usart, UsartX> fmt::Write for Transmitter<'usart, UsartX>
 where
+    Self: BlockingWriteDefault<u8>,
     UsartX: Peripheral,
 {
-    type Error = Error;
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        use crate::prelude::*;
 
-    fn read(&mut self) -> nb::Result<u8, Self::Error> {
-        let stat = self.0.usart.stat.read();
+        self.bwrite_all(s.as_bytes()).map_err(|_| fmt::Error)?;
+        block!(self.flush()).map_err(|_| fmt::Error)?;
 
-        if stat.rxbrk().bit_is_set() {
-            return Err(nb::Error::WouldBlock);
+        Ok(())
+    }
+}
+
+impl<'usart, UsartX> dma::Dest for Transmitter<'usart, UsartX>
+where
+    UsartX: Peripheral,
+{
+    type Error = Void;
+
+    fn wait(&mut self) -> nb::Result<(), Self::Error> {
+        self.flush()
+    }
+
+    fn end_addr(&mut self) -> *mut u8 {
+        &self.0.usart.txdat as *const _ as *mut TXDAT as *mut u8
+    }
+}
+
+fn read_usart(usart: &pac::usart0::RegisterBlock, config: Config) -> nb::Result<u8, Error> {
+    let stat = usart.stat.read();
+
+    if stat.rxbrk().bit_is_set() {
+        return Err(nb::Error::WouldBlock);
+    }
+    // TODO Due to SVD bug not available
+    #[cfg(feature = "82x")]
+    {
+        if stat.overrunint().bit_is_set() {
+            return Err(nb::Error::Other(Error::Overrun));
         }
-        // TODO Due to SVD bug not available
-        #[cfg(feature = "82x")]
-        {
-            if stat.overrunint().bit_is_set() {
-                return Err(nb::Error::Other(Error::Overrun));
-            }
+    }
+
+    if stat.rxrdy().bit_is_set() {
+        // It's important to read this register all at once, as reading it
+        // changes the status flags.
+        let rx_dat_stat = usart.rxdatstat.read();
+
+        if rx_dat_stat.framerr().bit_is_set() {
+            return Err(nb::Error::Other(Error::Framing));
+        }
+        if rx_dat_stat.parityerr().bit_is_set() {
+            return Err(nb::Error::Other(Error::Parity));
+        }
+        if rx_dat_stat.rxnoise().bit_is_set() {
+            return Err(nb::Error::Other(Error::Noise));
         }
 
-        if stat.rxrdy().bit_is_set() {
-            // It's important to read this register all at once, as reading
-            // it changes the status flags.
-            let rx_dat_stat = self.0.usart.rxdatstat.read();
+        // `bits` returns `u16`, but at most 9 bits are used.
+        let data = rx_dat_stat.rxdat().bits();
 
-            if rx_dat_stat.framerr().bit_is_set() {
-                return Err(nb::Error::Other(Error::Framing));
-            }
-            if rx_dat_stat.parityerr().bit_is_set() {
-                return Err(nb::Error::Other(Error::Parity));
-            }
-            if rx_dat_stat.rxnoise().bit_is_set() {
-                return Err(nb::Error::Other(Error::Noise));
+        if let Mode::MultiDrop { .. } = config.mode {
+            // In multidrop/RS-485 mode, the 9th bit marks this byte as an
+            // address rather than data (see user manual, section 13.8.11).
+            // Surface that to the caller instead of silently folding it into
+            // the data byte.
+            if data & 0x100 != 0 {
+                return Err(nb::Error::Other(Error::Address((data & 0xff) as u8)));
             }
 
-            // `bits` returns `u16`, but at most 9 bits are used. We've
-            // configured UART to use only 8 bits, so we can safely cast to
-            // `u8`.
-            return Ok(rx_dat_stat.rxdat().bits() as u8);
-        } else {
-            return Err(nb::Error::WouldBlock);
+            return Ok((data & 0xff) as u8);
         }
+
+        // Mask off anything beyond the configured data length, so callers
+        // don't see garbage in the upper bits when using 7-bit frames. As the
+        // result is a `u8`, the 9th data bit of a 9-bit frame is dropped.
+        let data = match config.data_len {
+            DataLength::Bits7 => data & 0x7f,
+            DataLength::Bits8 | DataLength::Bits9 => data & 0xff,
+        };
+        Ok(data as u8)
+    } else {
+        Err(nb::Error::WouldBlock)
     }
 }
 
-/// USART transmitter
-pub struct Transmitter<'usart, UsartX: 'usart>(&'usart USART<UsartX>);
+fn write_usart(usart: &pac::usart0::RegisterBlock, word: u8) -> nb::Result<(), Void> {
+    if usart.stat.read().txrdy().bit_is_clear() {
+        return Err(nb::Error::WouldBlock);
+    }
 
-impl<'usart, UsartX> Transmitter<'usart, UsartX>
+    unsafe {
+        usart.txdat.write(|w| w.txdat().bits(word as u16));
+    }
+
+    Ok(())
+}
+
+fn flush_usart(usart: &pac::usart0::RegisterBlock) -> nb::Result<(), Void> {
+    if usart.stat.read().txidle().bit_is_clear() {
+        return Err(nb::Error::WouldBlock);
+    }
+
+    Ok(())
+}
+
+// Drives `pin` high for the duration of `f`, then waits for the line to go
+// idle before releasing it again. Shared between `Transmitter` and `Tx`,
+// which otherwise differ only in the type they pass as `tx`.
+//
+// `pin.set_high`/`set_low` errors are ignored: the write error type of both
+// `Transmitter` and `Tx` is `Void`, so neither half has a way to report a
+// driver-enable pin that fails to toggle.
+fn write_with_driver_enable<W, P>(tx: &mut W, pin: &mut P, f: impl FnOnce(&mut W))
+where
+    W: Write<u8, Error = Void>,
+    P: OutputPin,
+{
+    let _ = pin.set_high();
+    f(tx);
+    let _ = block!(tx.flush());
+    let _ = pin.set_low();
+}
+
+/// The transmitter half of a [`USART`] split via [`USART::split`]
+///
+/// Unlike [`Transmitter`], this is an owned, `Send` handle that doesn't
+/// borrow from the `USART`, so it can be moved into a different task or
+/// interrupt handler than its [`Rx`] counterpart.
+///
+/// [`USART`]: struct.USART.html
+/// [`USART::split`]: struct.USART.html#method.split
+/// [`Transmitter`]: struct.Transmitter.html
+/// [`Rx`]: struct.Rx.html
+pub struct Tx<UsartX> {
+    _usart: PhantomData<UsartX>,
+}
+
+unsafe impl<UsartX> Send for Tx<UsartX> {}
+
+impl<UsartX> Tx<UsartX>
 where
     UsartX: Peripheral,
 {
+    fn usart(&self) -> &pac::usart0::RegisterBlock {
+        unsafe { &*UsartX::ptr() }
+    }
+
     /// Enable the TXRDY interrupt
     ///
     /// The interrupt will not actually work unless the interrupts for this
     /// peripheral have also been enabled via the NVIC. See
-    /// [`enable_interrupts`].
+    /// [`USART::enable_interrupts`].
     ///
-    /// [`enable_interrupts`]: #method.enable_interrupts
+    /// [`USART::enable_interrupts`]: struct.USART.html#method.enable_interrupts
     pub fn enable_txrdy_interrupt(&mut self) {
-        self.0.usart.intenset.write(|w| w.txrdyen().set_bit());
+        self.usart().intenset.write(|w| w.txrdyen().set_bit());
+    }
+
+    /// Drive `pin` high for the duration of `f`, then wait for the line to
+    /// go idle before releasing it again
+    ///
+    /// See [`Transmitter::write_with_driver_enable`] for details.
+    ///
+    /// [`Transmitter::write_with_driver_enable`]: struct.Transmitter.html#method.write_with_driver_enable
+    pub fn write_with_driver_enable<P>(&mut self, pin: &mut P, f: impl FnOnce(&mut Self))
+    where
+        P: OutputPin,
+    {
+        write_with_driver_enable(self, pin, f)
     }
 
     /// Disable the TXRDY interrupt
     pub fn disable_txrdy_interrupt(&mut self) {
-        self.0.usart.intenclr.write(|w| w.txrdyclr().set_bit());
+        self.usart().intenclr.write(|w| w.txrdyclr().set_bit());
     }
 }
 
-impl<'usart, UsartX> Write<u8> for Transmitter<'usart, UsartX>
+impl<UsartX> Write<u8> for Tx<UsartX>
 where
     UsartX: Peripheral,
 {
     type Error = Void;
 
     fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
-        if self.0.usart.stat.read().txrdy().bit_is_clear() {
-            return Err(nb::Error::WouldBlock);
-        }
-
-        unsafe {
-            self.0.usart.txdat.write(|w| w.txdat().bits(word as u16));
-        }
-
-        Ok(())
+        write_usart(self.usart(), word)
     }
 
     fn flush(&mut self) -> nb::Result<(), Self::Error> {
-        if self.0.usart.stat.read().txidle().bit_is_clear() {
-            return Err(nb::Error::WouldBlock);
-        }
-
-        Ok(())
+        flush_usart(self.usart())
     }
 }
 
-impl<'usart, UsartX> BlockingWriteDefault<u8> for Transmitter<'usart, UsartX> where
-    UsartX: Peripheral
-{
-}
+impl<UsartX> BlockingWriteDefault<u8> for Tx<UsartX> where UsartX: Peripheral {}
 
-impl<'usart, UsartX> fmt::Write for Transmitter<'usart, UsartX>
+impl<UsartX> fmt::Write for Tx<UsartX>
 where
     Self: BlockingWriteDefault<u8>,
     UsartX: Peripheral,
@@ -376,7 +854,7 @@ where
     }
 }
 
-impl<'usart, UsartX> dma::Dest for Transmitter<'usart, UsartX>
+impl<UsartX> dma::Dest for Tx<UsartX>
 where
     UsartX: Peripheral,
 {
@@ -387,7 +865,105 @@ where
     }
 
     fn end_addr(&mut self) -> *mut u8 {
-        &self.0.usart.txdat as *const _ as *mut TXDAT as *mut u8
+        &self.usart().txdat as *const _ as *mut TXDAT as *mut u8
+    }
+}
+
+/// The receiver half of a [`USART`] split via [`USART::split`]
+///
+/// Unlike [`Receiver`], this is an owned, `Send` handle that doesn't borrow
+/// from the `USART`, so it can be moved into a different task or interrupt
+/// handler than its [`Tx`] counterpart.
+///
+/// [`USART`]: struct.USART.html
+/// [`USART::split`]: struct.USART.html#method.split
+/// [`Receiver`]: struct.Receiver.html
+/// [`Tx`]: struct.Tx.html
+pub struct Rx<UsartX> {
+    config: Config,
+    _usart: PhantomData<UsartX>,
+}
+
+unsafe impl<UsartX> Send for Rx<UsartX> {}
+
+impl<UsartX> Rx<UsartX>
+where
+    UsartX: Peripheral,
+{
+    fn usart(&self) -> &pac::usart0::RegisterBlock {
+        unsafe { &*UsartX::ptr() }
+    }
+
+    /// Enable the RXRDY interrupt
+    ///
+    /// The interrupt will not actually work unless the interrupts for this
+    /// peripheral have also been enabled via the NVIC. See
+    /// [`USART::enable_interrupts`].
+    ///
+    /// [`USART::enable_interrupts`]: struct.USART.html#method.enable_interrupts
+    pub fn enable_rxrdy_interrupt(&mut self) {
+        self.usart().intenset.write(|w| w.rxrdyen().set_bit());
+    }
+
+    /// Disable the RXRDY interrupt
+    pub fn disable_rxrdy_interrupt(&mut self) {
+        self.usart().intenclr.write(|w| w.rxrdyclr().set_bit());
+    }
+
+    /// Stop matching address bytes, to let the following data bytes through
+    ///
+    /// Equivalent to [`USART::disable_address_detection`], for callers that
+    /// have moved this `Rx` out via [`USART::split`] and therefore no longer
+    /// have the `USART` value to call it on. See [`Mode::MultiDrop`] for the
+    /// full receive sequence.
+    ///
+    /// [`USART::disable_address_detection`]: struct.USART.html#method.disable_address_detection
+    /// [`USART::split`]: struct.USART.html#method.split
+    /// [`Mode::MultiDrop`]: enum.Mode.html#variant.MultiDrop
+    pub fn disable_address_detection(&mut self) {
+        self.usart().ctl.modify(|_, w| w.addrdet().disabled());
+    }
+
+    /// Resume matching address bytes, after [`disable_address_detection`]
+    ///
+    /// Equivalent to [`USART::enable_address_detection`], for callers that
+    /// have moved this `Rx` out via [`USART::split`].
+    ///
+    /// [`disable_address_detection`]: #method.disable_address_detection
+    /// [`USART::enable_address_detection`]: struct.USART.html#method.enable_address_detection
+    /// [`USART::split`]: struct.USART.html#method.split
+    pub fn enable_address_detection(&mut self) {
+        self.usart().ctl.modify(|_, w| w.addrdet().enabled());
+    }
+}
+
+impl<UsartX> Read<u8> for Rx<UsartX>
+where
+    UsartX: Peripheral,
+{
+    type Error = Error;
+
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        read_usart(self.usart(), self.config)
+    }
+}
+
+impl<UsartX> dma::Source for Rx<UsartX>
+where
+    UsartX: Peripheral,
+{
+    type Error = Void;
+
+    fn wait(&mut self) -> nb::Result<(), Self::Error> {
+        if self.usart().stat.read().rxrdy().bit_is_clear() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        Ok(())
+    }
+
+    fn start_addr(&mut self) -> *const u8 {
+        &self.usart().rxdat as *const _ as *const RXDAT as *const u8
     }
 }
 
@@ -407,6 +983,31 @@ pub trait Peripheral:
 
     /// The movable function that needs to be assigned to this USART's TX pin
     type Tx;
+
+    /// The movable function that needs to be assigned to this USART's CTS pin
+    ///
+    /// Used with [`USART::enable_with_flow_control`].
+    ///
+    /// [`USART::enable_with_flow_control`]: struct.USART.html#method.enable_with_flow_control
+    type Cts;
+
+    /// The movable function that needs to be assigned to this USART's RTS pin
+    ///
+    /// Used with [`USART::enable_with_flow_control`].
+    ///
+    /// [`USART::enable_with_flow_control`]: struct.USART.html#method.enable_with_flow_control
+    type Rts;
+
+    /// Returns a pointer to this USART's register block
+    ///
+    /// Used internally by the owned [`Tx`]/[`Rx`] halves returned by
+    /// [`USART::split`] to access their registers without holding a
+    /// reference to the whole peripheral.
+    ///
+    /// [`Tx`]: struct.Tx.html
+    /// [`Rx`]: struct.Rx.html
+    /// [`USART::split`]: struct.USART.html#method.split
+    fn ptr() -> *const pac::usart0::RegisterBlock;
 }
 
 impl Peripheral for pac::USART0 {
@@ -414,6 +1015,13 @@ impl Peripheral for pac::USART0 {
 
     type Rx = swm::U0_RXD;
     type Tx = swm::U0_TXD;
+
+    type Cts = swm::U0_CTS;
+    type Rts = swm::U0_RTS;
+
+    fn ptr() -> *const pac::usart0::RegisterBlock {
+        pac::USART0::ptr()
+    }
 }
 
 impl Peripheral for pac::USART1 {
@@ -421,6 +1029,13 @@ impl Peripheral for pac::USART1 {
 
     type Rx = swm::U1_RXD;
     type Tx = swm::U1_TXD;
+
+    type Cts = swm::U1_CTS;
+    type Rts = swm::U1_RTS;
+
+    fn ptr() -> *const pac::usart0::RegisterBlock {
+        pac::USART1::ptr() as *const _
+    }
 }
 
 impl Peripheral for pac::USART2 {
@@ -428,6 +1043,13 @@ impl Peripheral for pac::USART2 {
 
     type Rx = swm::U2_RXD;
     type Tx = swm::U2_TXD;
+
+    type Cts = swm::U2_CTS;
+    type Rts = swm::U2_RTS;
+
+    fn ptr() -> *const pac::usart0::RegisterBlock {
+        pac::USART2::ptr() as *const _
+    }
 }
 
 #[cfg(feature = "845")]
@@ -438,6 +1060,13 @@ impl Peripheral for pac::USART3 {
 
     type Rx = swm::U3_RXD;
     type Tx = swm::U3_TXD;
+
+    type Cts = swm::U3_CTS;
+    type Rts = swm::U3_RTS;
+
+    fn ptr() -> *const pac::usart0::RegisterBlock {
+        pac::USART3::ptr() as *const _
+    }
 }
 
 #[cfg(feature = "845")]
@@ -446,11 +1075,269 @@ impl Peripheral for pac::USART4 {
 
     type Rx = swm::U4_RXD;
     type Tx = swm::U4_TXD;
+
+    type Cts = swm::U4_CTS;
+    type Rts = swm::U4_RTS;
+
+    fn ptr() -> *const pac::usart0::RegisterBlock {
+        pac::USART4::ptr() as *const _
+    }
+}
+
+/// A USART interrupt event
+///
+/// Used as an argument to [`USART::listen`], [`USART::unlisten`], and
+/// [`USART::is_event_triggered`].
+///
+/// [`USART::listen`]: struct.USART.html#method.listen
+/// [`USART::unlisten`]: struct.USART.html#method.unlisten
+/// [`USART::is_event_triggered`]: struct.USART.html#method.is_event_triggered
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Event {
+    /// A byte has been received and is ready to be read
+    RxReady,
+
+    /// The transmit buffer is ready to accept another byte
+    TxReady,
+
+    /// The transmitter has gone idle
+    TxIdle,
+
+    /// A byte was received while the receive buffer was still in use
+    Overrun,
+
+    /// A character was received with a stop bit missing at the expected
+    /// location
+    FramingError,
+
+    /// A character was received with a parity error
+    ParityError,
+
+    /// A corrupted character was received
+    RxNoise,
+
+    /// A change in the receiver break condition was detected
+    RxBreak,
+
+    /// A change was detected on the CTS input
+    ///
+    /// Only meaningful if hardware flow control has been enabled via
+    /// [`USART::enable_with_flow_control`].
+    ///
+    /// [`USART::enable_with_flow_control`]: struct.USART.html#method.enable_with_flow_control
+    DeltaCts,
+
+    /// The baud-rate auto-detector armed by [`USART::start_autobaud`] timed
+    /// out or saw a framing error on the sync character
+    ///
+    /// This only fires on *failure*. A successful detection clears
+    /// `CTL.autobaud` without raising this (or any other) STAT event, so
+    /// this event cannot be used by itself to learn that detection has
+    /// finished; poll [`USART::is_autobaud_active`] for that.
+    ///
+    /// [`USART::start_autobaud`]: struct.USART.html#method.start_autobaud
+    /// [`USART::is_autobaud_active`]: struct.USART.html#method.is_autobaud_active
+    AutoBaudError,
+}
+
+/// Configuration for the USART frame format
+///
+/// Used as an argument to [`USART::enable`]. Create an instance with
+/// [`Default::default`] and override whichever fields you need.
+///
+/// # Examples
+///
+/// ``` no_run
+/// use lpc82x_hal::usart::{Config, DataLength, Parity};
+///
+/// let config = Config {
+///     data_len: DataLength::Bits9,
+///     parity: Parity::Even,
+///     ..Config::default()
+/// };
+/// ```
+///
+/// [`USART::enable`]: struct.USART.html#method.enable
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Config {
+    /// Number of data bits per frame
+    ///
+    /// Ignored in [`Mode::MultiDrop`], which always uses 9 data bits.
+    ///
+    /// [`Mode::MultiDrop`]: enum.Mode.html#variant.MultiDrop
+    pub data_len: DataLength,
+
+    /// Parity mode
+    pub parity: Parity,
+
+    /// Number of stop bits per frame
+    pub stop_bits: StopBits,
+
+    /// Addressing mode
+    pub mode: Mode,
+
+    /// Enable internal loopback
+    ///
+    /// When set, TXD is internally looped back to RXD, so firmware can
+    /// exercise the whole USART datapath without anything connected to the
+    /// pins. Useful for self-test at bring-up.
+    pub loopback: bool,
+
+    /// Enable hardware baud-rate auto-detection on start-up
+    ///
+    /// When set, the auto-detector is already armed once the USART comes out
+    /// of [`enable`]; it can also be (re-)armed at any later point with
+    /// [`USART::start_autobaud`].
+    ///
+    /// [`enable`]: struct.USART.html#method.enable
+    /// [`USART::start_autobaud`]: struct.USART.html#method.start_autobaud
+    pub autobaud: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            data_len: DataLength::Bits8,
+            parity: Parity::None,
+            stop_bits: StopBits::Bits1,
+            mode: Mode::Standard,
+            loopback: false,
+            autobaud: false,
+        }
+    }
+}
+
+fn effective_data_len(config: &Config) -> DataLength {
+    match config.mode {
+        Mode::Standard => config.data_len,
+        Mode::MultiDrop { .. } => DataLength::Bits9,
+    }
+}
+
+/// USART addressing mode
+///
+/// Part of [`Config`].
+///
+/// [`Config`]: struct.Config.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Mode {
+    /// Standard point-to-point framing
+    Standard,
+
+    /// RS-485/multidrop 9-bit address detection
+    ///
+    /// Forces the frame format to 9 data bits: the 9th bit marks a frame as
+    /// an address byte, which precedes the data bytes belonging to it. While
+    /// address detection is active (the default once enabled, and again
+    /// after [`USART::enable_address_detection`]), [`Receiver::read`]/
+    /// [`Rx::read`] report address bytes as [`Error::Address`] rather than
+    /// handing them to the caller as data, and data bytes don't come through
+    /// at all.
+    ///
+    /// Receiving a message therefore looks like this:
+    ///
+    /// 1. Read address bytes (reported as [`Error::Address`]) until one
+    ///    matches this USART's own address (compare it yourself, or rely on
+    ///    hardware filtering if `auto_address` is `true`).
+    /// 2. Call `disable_address_detection`, so the data bytes that follow are
+    ///    delivered normally.
+    /// 3. Read the message's data bytes.
+    /// 4. Call `enable_address_detection` again before the next address byte
+    ///    is expected, to resume step 1.
+    ///
+    /// These toggles are available both on [`USART`] ([`USART::disable_address_detection`]/
+    /// [`USART::enable_address_detection`]) and, for a receiver moved out via
+    /// [`USART::split`], on [`Rx`] ([`Rx::disable_address_detection`]/
+    /// [`Rx::enable_address_detection`]), so the protocol stays usable from
+    /// either side of a split.
+    ///
+    /// [`USART`]: struct.USART.html
+    /// [`Receiver::read`]: struct.Receiver.html
+    /// [`Rx::read`]: struct.Rx.html
+    /// [`Rx`]: struct.Rx.html
+    /// [`Error::Address`]: enum.Error.html#variant.Address
+    /// [`USART::disable_address_detection`]: struct.USART.html#method.disable_address_detection
+    /// [`USART::enable_address_detection`]: struct.USART.html#method.enable_address_detection
+    /// [`USART::split`]: struct.USART.html#method.split
+    /// [`Rx::disable_address_detection`]: struct.Rx.html#method.disable_address_detection
+    /// [`Rx::enable_address_detection`]: struct.Rx.html#method.enable_address_detection
+    MultiDrop {
+        /// This USART's address on the bus
+        address: u8,
+
+        /// Whether the hardware should only raise RXRDY for address bytes
+        /// that match `address`
+        ///
+        /// If `false`, every address byte is raised, and it's up to the
+        /// caller to check it against `address` (or dispatch on it, for a
+        /// USART that listens to several addresses). Either way, address
+        /// detection still needs to be toggled by hand around the data
+        /// bytes that follow a match; see [`Mode::MultiDrop`] for the full
+        /// sequence.
+        ///
+        /// [`Mode::MultiDrop`]: enum.Mode.html#variant.MultiDrop
+        auto_address: bool,
+    },
+}
+
+/// Number of data bits per USART frame
+///
+/// Part of [`Config`].
+///
+/// [`Config`]: struct.Config.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DataLength {
+    /// 7 data bits
+    Bits7,
+
+    /// 8 data bits
+    Bits8,
+
+    /// 9 data bits
+    Bits9,
+}
+
+/// Parity mode for USART frames
+///
+/// Part of [`Config`].
+///
+/// [`Config`]: struct.Config.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Parity {
+    /// No parity bit is sent or expected
+    None,
+
+    /// An even parity bit is sent and checked
+    Even,
+
+    /// An odd parity bit is sent and checked
+    Odd,
+}
+
+/// Number of stop bits per USART frame
+///
+/// Part of [`Config`].
+///
+/// [`Config`]: struct.Config.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StopBits {
+    /// 1 stop bit
+    Bits1,
+
+    /// 2 stop bits
+    Bits2,
 }
 
 /// A USART error
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Error {
+    /// Received an address byte rather than a data byte
+    ///
+    /// Carries the received address. Only produced in [`Mode::MultiDrop`].
+    ///
+    /// [`Mode::MultiDrop`]: enum.Mode.html#variant.MultiDrop
+    Address(u8),
+
     /// Character received with a stop bit missing at the expected location
     Framing,
 